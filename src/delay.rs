@@ -0,0 +1,118 @@
+//! Blocking delays backed by a hardware timer
+
+use cast::{u16, u32};
+use embedded_hal::blocking::delay::{DelayMs, DelayUs};
+use stm32f30x::{TIM2, TIM3, TIM4, TIM6, TIM7};
+
+use rcc::{APB1, Clocks};
+
+/// A blocking delay backed by a hardware timer counting up to its reload value
+///
+/// Reconfigures the timer for the requested interval and busy-polls `UIF`,
+/// so prefer a spare `TIM6`/`TIM7` over tying up a general purpose timer.
+pub struct Delay<TIM> {
+    tim: TIM,
+    clocks: Clocks,
+}
+
+macro_rules! hal {
+    ($($TIM:ident: ($tim:ident, $timXen:ident, $timXrst:ident),)+) => {
+        $(
+            impl Delay<$TIM> {
+                /// Configures a TIM peripheral as a blocking delay provider
+                pub fn $tim(tim: $TIM, clocks: Clocks, apb1: &mut APB1) -> Self {
+                    // enable and reset peripheral to a clean slate state
+                    apb1.enr().modify(|_, w| w.$timXen().set_bit());
+                    apb1.rstr().modify(|_, w| w.$timXrst().set_bit());
+                    apb1.rstr().modify(|_, w| w.$timXrst().clear_bit());
+
+                    Delay { tim, clocks }
+                }
+
+                /// Releases the TIM peripheral
+                pub fn free(self) -> $TIM {
+                    self.tim
+                }
+
+                fn delay(&mut self, us: u32) {
+                    let cycles_per_us = self.clocks.pclk1().0
+                        * if self.clocks.ppre1() == 1 { 1 } else { 2 }
+                        / 1_000_000;
+
+                    // cap long delays by looping multiple full reloads, since a
+                    // single delay may need more ticks than the counter holds
+                    let mut ticks = u64::from(us) * u64::from(cycles_per_us);
+
+                    while ticks > 0 {
+                        let reload = if ticks > u64::from(u32::max_value()) {
+                            u32::max_value()
+                        } else {
+                            ticks as u32
+                        };
+                        ticks -= u64::from(reload);
+
+                        let psc = u16((reload - 1) / (1 << 16)).unwrap();
+                        self.tim.psc.write(|w| unsafe { w.psc().bits(psc) });
+
+                        let arr = u16(reload / u32(psc + 1)).unwrap();
+                        self.tim.arr.write(|w| unsafe { w.bits(u32(arr)) });
+
+                        self.tim.cnt.reset();
+                        // latch PSC/ARR and clear any stale UIF before starting
+                        self.tim.egr.write(|w| w.ug().set_bit());
+                        self.tim.sr.modify(|_, w| w.uif().clear_bit());
+                        self.tim.cr1.modify(|_, w| w.cen().set_bit());
+
+                        while self.tim.sr.read().uif().bit_is_clear() {}
+
+                        self.tim.cr1.modify(|_, w| w.cen().clear_bit());
+                    }
+                }
+            }
+
+            impl DelayUs<u32> for Delay<$TIM> {
+                fn delay_us(&mut self, us: u32) {
+                    self.delay(us)
+                }
+            }
+
+            impl DelayUs<u16> for Delay<$TIM> {
+                fn delay_us(&mut self, us: u16) {
+                    self.delay(u32(us))
+                }
+            }
+
+            impl DelayUs<u8> for Delay<$TIM> {
+                fn delay_us(&mut self, us: u8) {
+                    self.delay(u32(us))
+                }
+            }
+
+            impl DelayMs<u32> for Delay<$TIM> {
+                fn delay_ms(&mut self, ms: u32) {
+                    self.delay(ms.saturating_mul(1_000))
+                }
+            }
+
+            impl DelayMs<u16> for Delay<$TIM> {
+                fn delay_ms(&mut self, ms: u16) {
+                    self.delay(u32(ms) * 1_000)
+                }
+            }
+
+            impl DelayMs<u8> for Delay<$TIM> {
+                fn delay_ms(&mut self, ms: u8) {
+                    self.delay(u32(ms) * 1_000)
+                }
+            }
+        )+
+    }
+}
+
+hal! {
+    TIM2: (tim2, tim2en, tim2rst),
+    TIM3: (tim3, tim3en, tim3rst),
+    TIM4: (tim4, tim4en, tim4rst),
+    TIM6: (tim6, tim6en, tim6rst),
+    TIM7: (tim7, tim7en, tim7rst),
+}
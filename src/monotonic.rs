@@ -0,0 +1,75 @@
+//! RTIC monotonic timer
+//!
+//! Exposes `TIM2` (32-bit on this family) as a `rtic_monotonic::Monotonic`
+//! tick source so an `rtic::app(dispatchers = ...)` can schedule tasks
+//! without burning the SysTick.
+
+use cast::u16;
+use fugit::{TimerDurationU32, TimerInstantU32};
+use rtic_monotonic::Monotonic;
+use stm32f30x::TIM2;
+
+use rcc::{APB1, Clocks};
+
+/// Tick rate of the monotonic clock, matching the `Instant`/`Duration`
+/// microsecond resolution used below
+const TICK_HZ: u32 = 1_000_000;
+
+/// A `Monotonic` timer backed by the free-running 32-bit `TIM2` counter
+pub struct MonoTimer {
+    tim: TIM2,
+}
+
+impl MonoTimer {
+    /// Configures `TIM2` as a free-running monotonic tick source at 1 MHz
+    pub fn new(tim: TIM2, clocks: Clocks, apb1: &mut APB1) -> Self {
+        // enable and reset peripheral to a clean slate state
+        apb1.enr().modify(|_, w| w.tim2en().set_bit());
+        apb1.rstr().modify(|_, w| w.tim2rst().set_bit());
+        apb1.rstr().modify(|_, w| w.tim2rst().clear_bit());
+
+        let ticks = clocks.pclk1().0 * if clocks.ppre1() == 1 { 1 } else { 2 }
+            / TICK_HZ;
+        let psc = u16(ticks - 1).unwrap();
+        tim.psc.write(|w| unsafe { w.psc().bits(psc) });
+
+        // free-running: let CNT wrap through its full 32-bit range, which is
+        // exactly the range `Instant`/`Duration` can represent
+        tim.arr.write(|w| unsafe { w.bits(u32::max_value()) });
+
+        tim.egr.write(|w| w.ug().set_bit());
+        tim.cr1.modify(|_, w| w.cen().set_bit());
+
+        MonoTimer { tim }
+    }
+}
+
+impl Monotonic for MonoTimer {
+    type Instant = TimerInstantU32<1_000_000>;
+    type Duration = TimerDurationU32<1_000_000>;
+
+    const DISABLE_INTERRUPT_ON_EMPTY_QUEUE: bool = false;
+
+    fn now(&mut self) -> Self::Instant {
+        TimerInstantU32::from_ticks(self.tim.cnt.read().bits())
+    }
+
+    fn zero() -> Self::Instant {
+        TimerInstantU32::from_ticks(0)
+    }
+
+    unsafe fn reset(&mut self) {
+        self.tim.cnt.reset();
+    }
+
+    fn set_compare(&mut self, instant: Self::Instant) {
+        self.tim
+            .ccr1
+            .write(|w| unsafe { w.bits(instant.duration_since_epoch().ticks()) });
+        self.tim.dier.modify(|_, w| w.cc1ie().set_bit());
+    }
+
+    fn clear_compare_flag(&mut self) {
+        self.tim.sr.modify(|_, w| w.cc1if().clear_bit());
+    }
+}
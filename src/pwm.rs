@@ -0,0 +1,212 @@
+//! Pulse Width Modulation (PWM)
+//!
+//! Turns a general purpose timer (`TIM2`/`TIM3`/`TIM4`) into up to four PWM
+//! output channels driven from its capture/compare unit.
+
+use core::marker::PhantomData;
+
+use cast::{u16, u32};
+use embedded_hal::PwmPin;
+use stm32f30x::{TIM2, TIM3, TIM4};
+
+use rcc::{APB1, Clocks};
+use time::Hertz;
+
+/// Output compare channel 1
+pub struct C1;
+/// Output compare channel 2
+pub struct C2;
+/// Output compare channel 3
+pub struct C3;
+/// Output compare channel 4
+pub struct C4;
+
+/// Pins that can be used to drive the PWM channels of a timer
+///
+/// An implementor declares through the associated constants which `CHx`
+/// outputs its pin set drives; each constant that is `true` wires up the
+/// matching channel, and a constant left at its default `false` leaves that
+/// channel unused. This module provides no implementations of the trait.
+pub trait Pins<TIM> {
+    /// Whether channel 1 is driven by this pin set
+    const C1: bool = false;
+    /// Whether channel 2 is driven by this pin set
+    const C2: bool = false;
+    /// Whether channel 3 is driven by this pin set
+    const C3: bool = false;
+    /// Whether channel 4 is driven by this pin set
+    const C4: bool = false;
+}
+
+/// A single PWM output channel of a `TIM` peripheral
+pub struct PwmChannel<TIM, CHANNEL> {
+    _tim: PhantomData<TIM>,
+    _channel: PhantomData<CHANNEL>,
+}
+
+/// A TIM peripheral configured as a set of PWM output channels
+///
+/// Owns every channel handle produced alongside it, so releasing the TIM via
+/// [`Pwm::free`] drops them too instead of leaving stale channels that could
+/// still poke the peripheral after it has been reclaimed.
+pub struct Pwm<TIM> {
+    tim: TIM,
+    c1: Option<PwmChannel<TIM, C1>>,
+    c2: Option<PwmChannel<TIM, C2>>,
+    c3: Option<PwmChannel<TIM, C3>>,
+    c4: Option<PwmChannel<TIM, C4>>,
+}
+
+impl<TIM> Pwm<TIM> {
+    /// Channel 1, if wired up by the pin set passed to the constructor
+    pub fn channel1(&mut self) -> Option<&mut PwmChannel<TIM, C1>> {
+        self.c1.as_mut()
+    }
+
+    /// Channel 2, if wired up by the pin set passed to the constructor
+    pub fn channel2(&mut self) -> Option<&mut PwmChannel<TIM, C2>> {
+        self.c2.as_mut()
+    }
+
+    /// Channel 3, if wired up by the pin set passed to the constructor
+    pub fn channel3(&mut self) -> Option<&mut PwmChannel<TIM, C3>> {
+        self.c3.as_mut()
+    }
+
+    /// Channel 4, if wired up by the pin set passed to the constructor
+    pub fn channel4(&mut self) -> Option<&mut PwmChannel<TIM, C4>> {
+        self.c4.as_mut()
+    }
+
+    /// Releases the TIM peripheral, dropping all of its channel handles
+    pub fn free(self) -> TIM {
+        self.tim
+    }
+}
+
+macro_rules! hal {
+    ($($TIM:ident: ($tim:ident, $timXen:ident, $timXrst:ident),)+) => {
+        $(
+            /// Configures a TIM peripheral as a set of PWM output channels
+            pub fn $tim<PINS>(
+                tim: $TIM,
+                _pins: PINS,
+                clocks: Clocks,
+                apb1: &mut APB1,
+                freq: Hertz,
+            ) -> Pwm<$TIM>
+            where
+                PINS: Pins<$TIM>,
+            {
+                // enable and reset peripheral to a clean slate state
+                apb1.enr().modify(|_, w| w.$timXen().set_bit());
+                apb1.rstr().modify(|_, w| w.$timXrst().set_bit());
+                apb1.rstr().modify(|_, w| w.$timXrst().clear_bit());
+
+                let ticks = clocks.pclk1().0 * if clocks.ppre1() == 1 { 1 } else { 2 }
+                    / freq.0;
+
+                let psc = u16((ticks - 1) / (1 << 16)).unwrap();
+                tim.psc.write(|w| unsafe { w.psc().bits(psc) });
+
+                let arr = u16(ticks / u32(psc + 1)).unwrap();
+                tim.arr.write(|w| unsafe { w.bits(u32(arr)) });
+
+                if PINS::C1 {
+                    tim.ccmr1_output()
+                        .modify(|_, w| unsafe { w.oc1m().bits(0b110).oc1pe().set_bit() });
+                    tim.ccer.modify(|_, w| w.cc1e().set_bit());
+                }
+
+                if PINS::C2 {
+                    tim.ccmr1_output()
+                        .modify(|_, w| unsafe { w.oc2m().bits(0b110).oc2pe().set_bit() });
+                    tim.ccer.modify(|_, w| w.cc2e().set_bit());
+                }
+
+                if PINS::C3 {
+                    tim.ccmr2_output()
+                        .modify(|_, w| unsafe { w.oc3m().bits(0b110).oc3pe().set_bit() });
+                    tim.ccer.modify(|_, w| w.cc3e().set_bit());
+                }
+
+                if PINS::C4 {
+                    tim.ccmr2_output()
+                        .modify(|_, w| unsafe { w.oc4m().bits(0b110).oc4pe().set_bit() });
+                    tim.ccer.modify(|_, w| w.cc4e().set_bit());
+                }
+
+                // enable preload of ARR so the period only changes on an update event
+                tim.cr1.modify(|_, w| w.arpe().set_bit());
+
+                // latch PSC/ARR/CCRy before starting the counter
+                tim.egr.write(|w| w.ug().set_bit());
+                tim.cr1.modify(|_, w| w.cen().set_bit());
+
+                Pwm {
+                    tim,
+                    c1: if PINS::C1 { Some(PwmChannel { _tim: PhantomData, _channel: PhantomData }) } else { None },
+                    c2: if PINS::C2 { Some(PwmChannel { _tim: PhantomData, _channel: PhantomData }) } else { None },
+                    c3: if PINS::C3 { Some(PwmChannel { _tim: PhantomData, _channel: PhantomData }) } else { None },
+                    c4: if PINS::C4 { Some(PwmChannel { _tim: PhantomData, _channel: PhantomData }) } else { None },
+                }
+            }
+        )+
+    }
+}
+
+hal! {
+    TIM2: (tim2, tim2en, tim2rst),
+    TIM3: (tim3, tim3en, tim3rst),
+    TIM4: (tim4, tim4en, tim4rst),
+}
+
+macro_rules! pwm_pin_hal {
+    ($($TIM:ident: ($CH:ident, $ccr:ident, $ccXe:ident),)+) => {
+        $(
+            impl PwmPin for PwmChannel<$TIM, $CH> {
+                type Duty = u16;
+
+                fn disable(&mut self) {
+                    let tim = unsafe { &*$TIM::ptr() };
+                    tim.ccer.modify(|_, w| w.$ccXe().clear_bit());
+                }
+
+                fn enable(&mut self) {
+                    let tim = unsafe { &*$TIM::ptr() };
+                    tim.ccer.modify(|_, w| w.$ccXe().set_bit());
+                }
+
+                fn get_duty(&self) -> u16 {
+                    let tim = unsafe { &*$TIM::ptr() };
+                    u16(tim.$ccr.read().bits()).unwrap()
+                }
+
+                fn get_max_duty(&self) -> u16 {
+                    let tim = unsafe { &*$TIM::ptr() };
+                    u16(tim.arr.read().bits()).unwrap()
+                }
+
+                fn set_duty(&mut self, duty: u16) {
+                    let tim = unsafe { &*$TIM::ptr() };
+                    tim.$ccr.write(|w| unsafe { w.bits(u32(duty)) });
+                }
+            }
+        )+
+    }
+}
+
+pwm_pin_hal! {
+    TIM2: (C1, ccr1, cc1e),
+    TIM2: (C2, ccr2, cc2e),
+    TIM2: (C3, ccr3, cc3e),
+    TIM2: (C4, ccr4, cc4e),
+    TIM3: (C1, ccr1, cc1e),
+    TIM3: (C2, ccr2, cc2e),
+    TIM3: (C3, ccr3, cc3e),
+    TIM3: (C4, ccr4, cc4e),
+    TIM4: (C1, ccr1, cc1e),
+    TIM4: (C2, ccr2, cc2e),
+    TIM4: (C3, ccr3, cc3e),
+    TIM4: (C4, ccr4, cc4e),
+}
@@ -0,0 +1,94 @@
+//! Quadrature Encoder Interface (QEI)
+//!
+//! Configures a general purpose timer (`TIM2`/`TIM3`/`TIM4`) in encoder
+//! interface mode to decode a rotary/motor quadrature signal on its CH1/CH2
+//! inputs.
+
+use cast::u16;
+use stm32f30x::{TIM2, TIM3, TIM4};
+
+use rcc::APB1;
+
+/// Encoder counting direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Counting up
+    Upcounting,
+    /// Counting down
+    Downcounting,
+}
+
+/// Pins that can be used as the CH1/CH2 inputs of a timer's encoder interface
+pub trait Pins<TIM> {}
+
+/// A timer configured in quadrature encoder interface mode
+pub struct Qei<TIM> {
+    tim: TIM,
+}
+
+macro_rules! hal {
+    ($($TIM:ident: ($tim:ident, $timXen:ident, $timXrst:ident),)+) => {
+        $(
+            impl Qei<$TIM> {
+                /// Configures a TIM peripheral as a quadrature encoder interface
+                pub fn $tim<PINS>(tim: $TIM, _pins: PINS, apb1: &mut APB1) -> Self
+                where
+                    PINS: Pins<$TIM>,
+                {
+                    // enable and reset peripheral to a clean slate state
+                    apb1.enr().modify(|_, w| w.$timXen().set_bit());
+                    apb1.rstr().modify(|_, w| w.$timXrst().set_bit());
+                    apb1.rstr().modify(|_, w| w.$timXrst().clear_bit());
+
+                    // CH1/CH2 are inputs, each mapped to its own timer input (TI1/TI2)
+                    tim.ccmr1_input()
+                        .modify(|_, w| unsafe { w.cc1s().bits(0b01).cc2s().bits(0b01) });
+
+                    // count on both TI1 and TI2 edges
+                    tim.smcr.modify(|_, w| unsafe { w.sms().bits(0b011) });
+
+                    // both channels non-inverted, rising edge
+                    tim.ccer.modify(|_, w| {
+                        w.cc1p().clear_bit();
+                        w.cc1np().clear_bit();
+                        w.cc2p().clear_bit();
+                        w.cc2np().clear_bit()
+                    });
+
+                    // full range so the counter wraps around instead of overflowing
+                    tim.arr.write(|w| unsafe { w.bits(0xFFFF) });
+
+                    tim.cr1.modify(|_, w| w.cen().set_bit());
+
+                    Qei { tim }
+                }
+
+                /// Returns the current pulse count
+                pub fn count(&self) -> u16 {
+                    u16(self.tim.cnt.read().bits()).unwrap()
+                }
+
+                /// Returns the counting direction
+                pub fn direction(&self) -> Direction {
+                    if self.tim.cr1.read().dir().bit_is_clear() {
+                        Direction::Upcounting
+                    } else {
+                        Direction::Downcounting
+                    }
+                }
+
+                /// Releases the TIM peripheral
+                pub fn free(self) -> $TIM {
+                    self.tim.cr1.modify(|_, w| w.cen().clear_bit());
+                    self.tim
+                }
+            }
+        )+
+    }
+}
+
+hal! {
+    TIM2: (tim2, tim2en, tim2rst),
+    TIM3: (tim3, tim3en, tim3rst),
+    TIM4: (tim4, tim4en, tim4rst),
+}
@@ -1,7 +1,11 @@
 //! Timers
 
 use cast::{u16, u32};
+use cortex_m::peripheral::syst::SystClkSource;
+use cortex_m::peripheral::SYST;
+use embedded_hal::timer::{Cancel, CountDown, Periodic};
 use nb;
+use void::Void;
 use stm32f30x::{TIM2, TIM3, TIM4, TIM6, TIM7};
 
 use rcc::{APB1, Clocks};
@@ -21,6 +25,13 @@ pub enum Event {
     Update
 }
 
+/// Timer errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// Timer is disabled
+    Disabled,
+}
+
 macro_rules! hal {
     ($($TIM:ident: ($tim:ident, $timXen:ident, $timXrst:ident),)+) => {
         $(
@@ -29,25 +40,6 @@ macro_rules! hal {
 
                 // NOTE(allow) `w.psc().bits()` is safe for TIM{6,7} but not for TIM{2,3,4} due to
                 // some SVD omission
-                pub fn start(&mut self)
-                {
-                    // pause
-                    self.tim.cr1.modify(|_, w| w.cen().clear_bit());
-                    // restart counter
-                    self.tim.cnt.reset();
-
-                    // start counter
-                    self.tim.cr1.modify(|_, w| w.cen().set_bit());
-                }
-
-                pub fn wait(&mut self) -> nb::Result<(), !> {
-                    if self.tim.sr.read().uif().bit_is_clear() {
-                        Err(nb::Error::WouldBlock)
-                    } else {
-                        self.tim.sr.modify(|_, w| w.uif().clear_bit());
-                        Ok(())
-                    }
-                }
 
                 // XXX(why not name this `new`?) bummer: constructors need to have different names
                 // even if the `$TIM` are non overlapping (compare to the `free` function below
@@ -89,24 +81,6 @@ macro_rules! hal {
                     self.tim.dier.write(|w| w.uie().set_bit());
                 }
 
-                #[allow(unused_unsafe)]
-                pub fn config<T>(&mut self, timeout: T)
-                where
-                    T: Into<Hertz>,
-                {
-                    self.timeout = timeout.into();
-
-                    let frequency = self.timeout.0;
-                    let ticks = self.clocks.pclk1().0 * if self.clocks.ppre1() == 1 { 1 } else { 2 }
-                        / frequency;
-
-                    let psc = u16((ticks - 1) / (1 << 16)).unwrap();
-                    self.tim.psc.write(|w| unsafe { w.psc().bits(psc) });
-
-                    let arr = u16(ticks / u32(psc + 1)).unwrap();
-                    self.tim.arr.write(|w| unsafe { w.bits(u32(arr)) });
-                }
-
                 /// Stops listening for an `event`
                 pub fn unlisten(&mut self, event: Event) {
                     match event {
@@ -134,6 +108,68 @@ macro_rules! hal {
                     self.tim
                 }
             }
+
+            #[allow(unused_unsafe)]
+            impl CountDown for Timer<$TIM> {
+                type Time = Hertz;
+
+                fn start<T>(&mut self, timeout: T)
+                where
+                    T: Into<Hertz>,
+                {
+                    // pause
+                    self.tim.cr1.modify(|_, w| w.cen().clear_bit());
+                    // restart counter
+                    self.tim.cnt.reset();
+
+                    self.timeout = timeout.into();
+
+                    let frequency = self.timeout.0;
+                    let ticks = self.clocks.pclk1().0 * if self.clocks.ppre1() == 1 { 1 } else { 2 }
+                        / frequency;
+
+                    let psc = u16((ticks - 1) / (1 << 16)).unwrap();
+                    self.tim.psc.write(|w| unsafe { w.psc().bits(psc) });
+
+                    let arr = u16(ticks / u32(psc + 1)).unwrap();
+                    self.tim.arr.write(|w| unsafe { w.bits(u32(arr)) });
+
+                    // latch PSC/ARR by firing an update event before starting the counter
+                    self.tim.egr.write(|w| w.ug().set_bit());
+                    // UG also sets UIF, which would make the next `wait()` return
+                    // immediately; clear it so `wait()` reflects the real timeout
+                    self.tim.sr.modify(|_, w| w.uif().clear_bit());
+
+                    // start counter
+                    self.tim.cr1.modify(|_, w| w.cen().set_bit());
+                }
+
+                fn wait(&mut self) -> nb::Result<(), Void> {
+                    if self.tim.sr.read().uif().bit_is_clear() {
+                        Err(nb::Error::WouldBlock)
+                    } else {
+                        self.tim.sr.modify(|_, w| w.uif().clear_bit());
+                        Ok(())
+                    }
+                }
+            }
+
+            impl Periodic for Timer<$TIM> {}
+
+            impl Cancel for Timer<$TIM> {
+                type Error = Error;
+
+                fn cancel(&mut self) -> Result<(), Self::Error> {
+                    if self.tim.cr1.read().cen().bit_is_clear() {
+                        return Err(Error::Disabled);
+                    }
+
+                    // pause
+                    self.tim.cr1.modify(|_, w| w.cen().clear_bit());
+
+                    Ok(())
+                }
+            }
         )+
     }
 }
@@ -169,12 +205,71 @@ impl Timer<TIM2> {
             self.tim.arr.write(|w|  w.bits(u32(arr)) );
         }
     }
+}
 
-    pub fn stop(&mut self)
+impl Timer<SYST> {
+    /// Configures the SysTick as a periodic count down timer
+    pub fn syst<T>(mut syst: SYST, timeout: T, clocks: Clocks) -> Self
+    where
+        T: Into<Hertz>,
     {
-        // pause
-        self.tim.cr1.modify(|_, w| w.cen().clear_bit());
-        // restart counter
-        self.tim.cnt.reset();
+        syst.set_clock_source(SystClkSource::Core);
+
+        let mut timer = Timer {
+            tim: syst,
+            clocks,
+            timeout: Hertz(0),
+        };
+        timer.start(timeout);
+        timer
+    }
+
+    /// Starts listening for an `event`
+    pub fn listen(&mut self, event: Event) {
+        match event {
+            Event::TimeOut | Event::Update => self.tim.enable_interrupt(),
+        }
     }
-}
\ No newline at end of file
+
+    /// Stops listening for an `event`
+    pub fn unlisten(&mut self, event: Event) {
+        match event {
+            Event::TimeOut | Event::Update => self.tim.disable_interrupt(),
+        }
+    }
+
+    /// Releases the SYST peripheral
+    pub fn free(self) -> SYST {
+        self.tim
+    }
+}
+
+impl CountDown for Timer<SYST> {
+    type Time = Hertz;
+
+    fn start<T>(&mut self, timeout: T)
+    where
+        T: Into<Hertz>,
+    {
+        self.tim.disable_counter();
+
+        self.timeout = timeout.into();
+        let rvr = self.clocks.sysclk().0 / self.timeout.0 - 1;
+
+        assert!(rvr < (1 << 24));
+
+        self.tim.set_reload(rvr);
+        self.tim.clear_current();
+        self.tim.enable_counter();
+    }
+
+    fn wait(&mut self) -> nb::Result<(), Void> {
+        if self.tim.has_wrapped() {
+            Ok(())
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+}
+
+impl Periodic for Timer<SYST> {}
\ No newline at end of file